@@ -0,0 +1,205 @@
+use crate::plonk2::{CircuitBuilder2, PartialWitness2, SimpleGenerator, Target2};
+use crate::Field;
+
+/// Identifies one of a circuit's lookup tables, returned by `add_lookup_table`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct TableId(pub(crate) usize);
+
+/// One `add_lookup` call: the table being checked against, and the routable input that
+/// must appear somewhere in it.
+pub(crate) struct Lookup<F: Field> {
+    pub table: TableId,
+    pub input: Target2<F>,
+}
+
+impl<F: Field> CircuitBuilder2<F> {
+    /// Registers a fixed lookup table, returning an id to pass to `add_lookup`.
+    pub fn add_lookup_table(&mut self, values: Vec<F>) -> TableId {
+        let id = TableId(self.lookup_tables.len());
+        self.lookup_tables.push(values);
+        id
+    }
+
+    /// Constrains `input` to equal one of the values in `table`, via the plookup grand
+    /// product rather than one gate per possible value.
+    pub fn add_lookup(&mut self, table: TableId, input: Target2<F>) {
+        self.lookups.push(Lookup { table, input });
+    }
+
+    /// The tables registered via `add_lookup_table`, for the prover to build the sorted
+    /// list and grand product from.
+    pub(crate) fn lookup_tables(&self) -> &[Vec<F>] {
+        &self.lookup_tables
+    }
+
+    /// The lookups registered via `add_lookup`, in the order they were registered.
+    pub(crate) fn lookups(&self) -> &[Lookup<F>] {
+        &self.lookups
+    }
+
+    /// Finalizes every lookup registered so far: for each table with at least one `add_lookup`
+    /// call against it, pads its inputs `f` up to `table.len()` (by repeating the last one),
+    /// allocates the `sorted`/`z` targets, and registers the `LookupSortGenerator` and
+    /// `LookupGenerator` that fill them in at witness-generation time. `beta`/`gamma` are the
+    /// plookup challenges, already derived by the caller's transcript. Panics if a table has
+    /// more `add_lookup` calls against it than entries.
+    ///
+    /// Returns `Z_0` for each finalized table. `LookupGenerator` now checks the wraparound row
+    /// itself (the product must close back to 1, or it panics), so a sorted list/grand
+    /// product that doesn't actually witness `f`'s containment in `table` is caught at
+    /// generation time instead of silently accepted. What's still missing is an in-circuit
+    /// gate constraint enforcing that same relation against the prover's committed `z`
+    /// polynomial, so a prover could still skip `LookupGenerator` entirely and commit to a
+    /// `z` of its choosing; that needs `CircuitBuilder2` to grow the constraint-polynomial
+    /// plumbing that `evaluate_all_constraints_recursively` already has in the old
+    /// `CircuitBuilder` world (see `plonk_recursion.rs`), which doesn't exist yet for
+    /// `CircuitBuilder2`.
+    pub fn finalize_lookups(&mut self, beta: Target2<F>, gamma: Target2<F>) -> Vec<Target2<F>>
+    where
+        F: Ord,
+    {
+        let mut by_table: Vec<Vec<Target2<F>>> = vec![Vec::new(); self.lookup_tables.len()];
+        for lookup in &self.lookups {
+            by_table[lookup.table.0].push(lookup.input);
+        }
+
+        let mut z_zeros = Vec::new();
+        for (table_index, inputs) in by_table.into_iter().enumerate() {
+            if inputs.is_empty() {
+                continue;
+            }
+            let table = self.lookup_tables[table_index].clone();
+            let n = table.len();
+
+            let mut padded_inputs = inputs;
+            assert!(
+                padded_inputs.len() <= n,
+                "table {} has {} lookups but only {} entries",
+                table_index, padded_inputs.len(), n,
+            );
+            let last = *padded_inputs.last().unwrap();
+            padded_inputs.resize(n, last);
+
+            let sorted = self.add_virtual_advice_targets(2 * n);
+            let z = self.add_virtual_advice_targets(n);
+
+            self.add_generator(LookupSortGenerator {
+                table: table.clone(),
+                inputs: padded_inputs.clone(),
+                sorted: sorted.clone(),
+            });
+            z_zeros.push(z[0]);
+            self.add_generator(LookupGenerator { table, inputs: padded_inputs, sorted, z, beta, gamma });
+        }
+        z_zeros
+    }
+}
+
+/// Computes plookup's sorted list `s`: the concatenation of the (already padded) lookup
+/// inputs `f` and the table `t`, sorted so that every occurrence of a table value in `f` sits
+/// next to its copy in `t`. Implemented as a stable sort by each value's position in `t`,
+/// which is exactly what the grand-product identity in `LookupGenerator` needs: consecutive
+/// `s` entries are either both copies of the same table value, or a genuine `t_j`/`t_{j+1}`
+/// pair.
+pub(crate) struct LookupSortGenerator<F: Field> {
+    pub table: Vec<F>,
+    pub inputs: Vec<Target2<F>>,
+    pub sorted: Vec<Target2<F>>,
+}
+
+impl<F: Field + Ord> SimpleGenerator<F> for LookupSortGenerator<F> {
+    fn dependencies(&self) -> Vec<Target2<F>> {
+        self.inputs.clone()
+    }
+
+    fn run_once(&mut self, witness: &PartialWitness2<F>) -> PartialWitness2<F> {
+        use std::collections::HashMap;
+
+        let table_position: HashMap<F, usize> =
+            self.table.iter().enumerate().map(|(i, &t)| (t, i)).collect();
+        let f: Vec<F> = self.inputs.iter().map(|&t| witness.get(t)).collect();
+
+        let mut combined: Vec<F> = self.table.iter().copied().chain(f).collect();
+        combined.sort_by_key(|v| table_position.get(v).copied().unwrap_or(usize::MAX));
+
+        let mut result = PartialWitness2::new();
+        for (&target, value) in self.sorted.iter().zip(combined) {
+            result.set(target, value);
+        }
+        result
+    }
+}
+
+/// Computes the plookup sorted list `s` (the sorted concatenation of the lookup inputs `f`
+/// and the table `t`) and the grand product `Z` witnessing that every value in `f` also
+/// appears in `t`, given challenges `beta`/`gamma` squeezed from the transcript. Assumes `f`
+/// has already been padded to `table.len()` (by repeating its last entry), so every vector
+/// here is indexed consistently by the table's degree.
+///
+/// `Z_0 = 1`, and
+/// `Z_{j+1} = Z_j * (1+beta)*(gamma+f_j) * (gamma*(1+beta)+t_j+beta*t_{j+1})
+///                 / ((gamma*(1+beta)+s_j+beta*s_{j+1}) * (gamma*(1+beta)+s_{n+j}+beta*s_{n+j+1}))`,
+/// with `Z_0` set to 1 here. No `L_1` boundary constraint on `Z_0` is added in-circuit;
+/// `finalize_lookups` only returns each table's `Z_0` target.
+pub(crate) struct LookupGenerator<F: Field> {
+    pub table: Vec<F>,
+    /// `f`, padded to `table.len()`.
+    pub inputs: Vec<Target2<F>>,
+    /// The sorted concatenation of `inputs` and `table`, of length `2 * table.len()`.
+    pub sorted: Vec<Target2<F>>,
+    pub z: Vec<Target2<F>>,
+    pub beta: Target2<F>,
+    pub gamma: Target2<F>,
+}
+
+impl<F: Field> SimpleGenerator<F> for LookupGenerator<F> {
+    fn dependencies(&self) -> Vec<Target2<F>> {
+        let mut deps = self.inputs.clone();
+        deps.extend_from_slice(&self.sorted);
+        deps.push(self.beta);
+        deps.push(self.gamma);
+        deps
+    }
+
+    fn run_once(&mut self, witness: &PartialWitness2<F>) -> PartialWitness2<F> {
+        let beta = witness.get(self.beta);
+        let gamma = witness.get(self.gamma);
+        let one_plus_beta = F::ONE + beta;
+        let gamma_term = gamma * one_plus_beta;
+
+        let f: Vec<F> = self.inputs.iter().map(|&t| witness.get(t)).collect();
+        let s: Vec<F> = self.sorted.iter().map(|&t| witness.get(t)).collect();
+        let n = self.table.len();
+
+        let mut result = PartialWitness2::new();
+        let mut z = F::ONE;
+        result.set(self.z[0], z);
+        for j in 0..n - 1 {
+            let numerator = one_plus_beta
+                * (gamma + f[j])
+                * (gamma_term + self.table[j] + beta * self.table[j + 1]);
+            let denominator = (gamma_term + s[j] + beta * s[j + 1])
+                * (gamma_term + s[n + j] + beta * s[n + j + 1]);
+            z = z * numerator / denominator;
+            result.set(self.z[j + 1], z);
+        }
+
+        // The grand product only proves `f` is contained in `table` if it closes back to 1
+        // across the wraparound row (row `n - 1` back to row 0); that's the row this loop
+        // never computes since `z` only has `n` slots. Without this, a prover could hand back
+        // any `sorted`/`z` pair consistent with the rest of the trace and nothing would
+        // notice the multiset relation doesn't actually hold.
+        let wraparound_numerator =
+            one_plus_beta * (gamma + f[n - 1]) * (gamma_term + self.table[n - 1] + beta * self.table[0]);
+        let wraparound_denominator = (gamma_term + s[n - 1] + beta * s[n])
+            * (gamma_term + s[2 * n - 1] + beta * s[0]);
+        let closing_z = z * wraparound_numerator / wraparound_denominator;
+        assert_eq!(
+            closing_z,
+            F::ONE,
+            "plookup grand product didn't close back to 1 -- some input isn't in its table",
+        );
+
+        result
+    }
+}
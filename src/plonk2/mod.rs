@@ -10,21 +10,38 @@ pub use prover::*;
 pub use verifier::*;
 pub use witness::*;
 
-use crate::{Field, GateInstance, Gate2, Wire, GateWrapper};
-use std::collections::HashSet;
+use crate::{Field, GateInstance, Gate2, Wire, GateWrapper, NUM_WIRES};
+use std::collections::{HashMap, HashSet};
+use lookup::Lookup;
+use partitions::Forest;
 
 mod constraint_polynomial;
 mod gate;
 mod generator;
+mod lookup;
 mod partitions;
 mod prover;
 mod witness;
 mod verifier;
 
+pub use lookup::*;
+pub(crate) use partitions::*;
+
 pub struct CircuitBuilder2<F: Field> {
     gates: HashSet<GateWrapper<F>>,
     gate_instances: Vec<GateInstance<F>>,
     generators: Vec<Box<dyn WitnessGenerator2<F>>>,
+    /// Tracks which routable targets have been constrained equal via `assert_equal`, so the
+    /// permutation argument's wire permutation `sigma` can be derived from it at build time.
+    copy_constraints: Forest<F>,
+    /// Tables registered via `add_lookup_table`.
+    lookup_tables: Vec<Vec<F>>,
+    /// Lookups registered via `add_lookup`.
+    lookups: Vec<Lookup<F>>,
+    /// The next index to hand out from `add_virtual_advice_target`.
+    virtual_advice_target_index: usize,
+    /// The next index to hand out from `constant`.
+    constant_index: usize,
 }
 
 impl<F: Field> CircuitBuilder2<F> {
@@ -33,9 +50,29 @@ impl<F: Field> CircuitBuilder2<F> {
             gates: HashSet::new(),
             gate_instances: Vec::new(),
             generators: Vec::new(),
+            copy_constraints: Forest::new(),
+            lookup_tables: Vec::new(),
+            lookups: Vec::new(),
+            virtual_advice_target_index: 0,
+            constant_index: 0,
         }
     }
 
+    /// Returns a fresh, non-routable target for a generator to fill in at witness-generation
+    /// time. Unlike `zero`/`one`/`constant`, these aren't wired into the permutation
+    /// argument, so they're only suitable for values a generator produces and only other
+    /// generators (not gate constraints) need to read back, e.g. plookup's sorted list.
+    pub fn add_virtual_advice_target(&mut self) -> Target2<F> {
+        let index = self.virtual_advice_target_index;
+        self.virtual_advice_target_index += 1;
+        Target2::VirtualAdviceTarget { index }
+    }
+
+    /// Returns `n` fresh virtual advice targets.
+    pub fn add_virtual_advice_targets(&mut self, n: usize) -> Vec<Target2<F>> {
+        (0..n).map(|_| self.add_virtual_advice_target()).collect()
+    }
+
     /// Adds a gate to the circuit, and returns its index.
     pub fn add_gate(&mut self, gate_instance: GateInstance<F>) -> usize {
         let index = self.gate_instances.len();
@@ -76,6 +113,62 @@ impl<F: Field> CircuitBuilder2<F> {
     pub fn assert_equal(&mut self, x: Target2<F>, y: Target2<F>) {
         assert!(x.is_routable());
         assert!(y.is_routable());
+        self.copy_constraints.merge(x, y);
+    }
+
+    /// Returns the routing cycles induced by every `assert_equal` call made so far, i.e.
+    /// the classes the permutation argument's wire permutation `sigma` is built from.
+    pub(crate) fn copy_constraint_cycles(&mut self) -> Vec<Vec<Target2<F>>> {
+        self.copy_constraints.cycles()
+    }
+
+    /// Finalizes the copy constraints recorded via `assert_equal`: computes the wire
+    /// permutation `sigma` from the routing cycles, and registers the `ZGenerator` that fills
+    /// in the permutation argument's grand product at witness-generation time, so `sigma` is
+    /// actually consumed rather than just recorded. Returns `sigma` for the prover to commit
+    /// to alongside the other preprocessed polynomials.
+    ///
+    /// `z_values` are the wire targets the grand product should be written to, one per row.
+    /// `beta`/`gamma` are the permutation argument's challenges, already derived by the
+    /// caller's transcript.
+    pub fn finalize_copy_constraints(
+        &mut self,
+        z_values: Vec<Target2<F>>,
+        beta: Target2<F>,
+        gamma: Target2<F>,
+    ) -> Vec<Vec<F>> {
+        let degree = self.gate_instances.len();
+        // `EvaluationDomain` is the one place that owns a domain's subgroup generator; deriving
+        // it by hand here would risk drifting from whatever domain the prover actually runs its
+        // FFTs over.
+        let domain = crate::poly::EvaluationDomain::<F>::new(degree.trailing_zeros() as usize, 0);
+        let subgroup_generator = domain.generator();
+        let k = coset_shifts::<F>(NUM_WIRES);
+
+        let cycles = self.copy_constraint_cycles();
+        let mut slot_of_target = HashMap::new();
+        for target in cycles.iter().flatten() {
+            if let Target2::Wire(wire) = target {
+                slot_of_target.insert(*target, WireSlot { wire_index: wire.input, row: wire.gate });
+            }
+        }
+        let sigma = sigma_from_cycles(&slot_of_target, &cycles, NUM_WIRES, degree, subgroup_generator);
+
+        let wire_values: Vec<Vec<Target2<F>>> = (0..degree)
+            .map(|row| (0..NUM_WIRES).map(|col| Target2::wire(row, col)).collect())
+            .collect();
+
+        self.add_generator(ZGenerator {
+            wire_values,
+            z_values,
+            sigma: sigma.clone(),
+            k,
+            subgroup_generator,
+            beta,
+            gamma,
+        });
+
+        sigma
     }
 
     pub fn add_generator<G: WitnessGenerator2<F>>(&mut self, generator: G) {
@@ -104,7 +197,26 @@ impl<F: Field> CircuitBuilder2<F> {
 
     /// Returns a routable target with the given constant value.
     pub fn constant(&mut self, c: F) -> Target2<F> {
-        todo!()
+        struct ConstantGenerator<F: Field> {
+            target: Target2<F>,
+            value: F,
+        }
+
+        impl<F: Field> SimpleGenerator<F> for ConstantGenerator<F> {
+            fn dependencies(&self) -> Vec<Target2<F>> {
+                Vec::new()
+            }
+
+            fn run_once(&mut self, _witness: &PartialWitness2<F>) -> PartialWitness2<F> {
+                PartialWitness2::singleton(self.target, self.value)
+            }
+        }
+
+        let index = self.constant_index;
+        self.constant_index += 1;
+        let target = Target2::Constant { index };
+        self.add_generator(ConstantGenerator { target, value: c });
+        target
     }
 }
 
@@ -114,6 +226,9 @@ pub enum Target2<F: Field> {
     Wire(Wire),
     PublicInput { index: usize },
     VirtualAdviceTarget { index: usize },
+    /// A target allocated by `constant`, routable so it can be wired into the permutation
+    /// argument like any other value the prover commits to.
+    Constant { index: usize },
     // Trick taken from https://github.com/rust-lang/rust/issues/32739#issuecomment-627765543.
     _Field(Infallible, PhantomData<F>),
 }
@@ -128,6 +243,7 @@ impl<F: Field> Target2<F> {
             Target2::Wire(wire) => wire.is_routable(),
             Target2::PublicInput { .. } => true,
             Target2::VirtualAdviceTarget { .. } => false,
+            Target2::Constant { .. } => true,
             Target2::_Field(_, _) => unreachable!(),
         }
     }
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+use crate::plonk2::{PartialWitness2, SimpleGenerator, Target2};
+use crate::Field;
+
+/// A disjoint-set forest over the routable `Target2`s that `assert_equal` has constrained
+/// equal. Each `merge` unions two targets' classes; `cycles` reads the classes back out so
+/// the builder can turn them into a wire permutation `sigma`.
+pub(crate) struct Forest<F: Field> {
+    indices: HashMap<Target2<F>, usize>,
+    targets: Vec<Target2<F>>,
+    parents: Vec<usize>,
+}
+
+impl<F: Field> Forest<F> {
+    pub fn new() -> Self {
+        Forest {
+            indices: HashMap::new(),
+            targets: Vec::new(),
+            parents: Vec::new(),
+        }
+    }
+
+    fn index_of(&mut self, target: Target2<F>) -> usize {
+        if let Some(&i) = self.indices.get(&target) {
+            return i;
+        }
+        let i = self.parents.len();
+        self.indices.insert(target, i);
+        self.targets.push(target);
+        self.parents.push(i);
+        i
+    }
+
+    fn find(&mut self, i: usize) -> usize {
+        if self.parents[i] != i {
+            let root = self.find(self.parents[i]);
+            self.parents[i] = root;
+        }
+        self.parents[i]
+    }
+
+    /// Unions the classes containing `x` and `y`.
+    pub fn merge(&mut self, x: Target2<F>, y: Target2<F>) {
+        let (xi, yi) = (self.index_of(x), self.index_of(y));
+        let (xr, yr) = (self.find(xi), self.find(yi));
+        if xr != yr {
+            self.parents[xr] = yr;
+        }
+    }
+
+    /// Groups every target seen so far by its class, giving the routing cycles induced by
+    /// the `merge`s applied so far. A target only ever merged with itself forms its own
+    /// length-1 cycle; a target never passed to `merge` doesn't appear at all.
+    pub fn cycles(&mut self) -> Vec<Vec<Target2<F>>> {
+        let mut by_root: HashMap<usize, Vec<Target2<F>>> = HashMap::new();
+        for i in 0..self.targets.len() {
+            let root = self.find(i);
+            by_root.entry(root).or_insert_with(Vec::new).push(self.targets[i]);
+        }
+        by_root.into_values().collect()
+    }
+}
+
+/// A wire slot's location in the trace: wire column `wire_index`, row `row`.
+#[derive(Copy, Clone)]
+pub(crate) struct WireSlot {
+    pub wire_index: usize,
+    pub row: usize,
+}
+
+/// The `k_i` coset shifts used to give each wire column its own copy of the evaluation
+/// domain `<g>`, so the identity label `k_i * g^j` is unique across every
+/// `(wire_index, row)` pair. `k_0 = 1`, i.e. wire column 0 uses the domain itself.
+pub(crate) fn coset_shifts<F: Field>(num_wire_columns: usize) -> Vec<F> {
+    (0..num_wire_columns)
+        .map(|i| F::MULTIPLICATIVE_GROUP_GENERATOR.exp_usize(i))
+        .collect()
+}
+
+/// Turns the routing cycles recorded by a `Forest` into the sigma permutation, given as
+/// `sigma[wire_index][row]`: for every wire slot, the identity label of the slot it's
+/// routed to. Slots that aren't part of any cycle route to themselves.
+pub(crate) fn sigma_from_cycles<F: Field>(
+    slot_of_target: &HashMap<Target2<F>, WireSlot>,
+    cycles: &[Vec<Target2<F>>],
+    num_wire_columns: usize,
+    degree: usize,
+    subgroup_generator: F,
+) -> Vec<Vec<F>> {
+    let k = coset_shifts::<F>(num_wire_columns);
+    let identity_label = |slot: WireSlot| k[slot.wire_index] * subgroup_generator.exp_usize(slot.row);
+
+    let mut sigma: Vec<Vec<F>> = (0..num_wire_columns)
+        .map(|i| (0..degree).map(|j| k[i] * subgroup_generator.exp_usize(j)).collect())
+        .collect();
+
+    for cycle in cycles {
+        let slots: Vec<WireSlot> = cycle
+            .iter()
+            .filter_map(|t| slot_of_target.get(t).copied())
+            .collect();
+        if slots.len() < 2 {
+            continue;
+        }
+        for (idx, &slot) in slots.iter().enumerate() {
+            let next = slots[(idx + 1) % slots.len()];
+            sigma[slot.wire_index][slot.row] = identity_label(next);
+        }
+    }
+    sigma
+}
+
+/// Computes the permutation argument's grand product `Z` from the partial witness.
+/// `Z_0 = 1`, and `Z_{j+1} = Z_j * prod_i (w_{i,j} + beta*id_{i,j} + gamma)
+///                                / (w_{i,j} + beta*sigma_{i,j} + gamma)`.
+pub(crate) struct ZGenerator<F: Field> {
+    /// `wire_values[row][wire_index]`.
+    pub wire_values: Vec<Vec<Target2<F>>>,
+    /// `z_values[row]`, the targets this generator is responsible for filling in.
+    pub z_values: Vec<Target2<F>>,
+    /// `sigma[wire_index][row]`.
+    pub sigma: Vec<Vec<F>>,
+    pub k: Vec<F>,
+    pub subgroup_generator: F,
+    pub beta: Target2<F>,
+    pub gamma: Target2<F>,
+}
+
+impl<F: Field> SimpleGenerator<F> for ZGenerator<F> {
+    fn dependencies(&self) -> Vec<Target2<F>> {
+        let mut deps: Vec<Target2<F>> = self.wire_values.iter().flatten().copied().collect();
+        deps.push(self.beta);
+        deps.push(self.gamma);
+        deps
+    }
+
+    fn run_once(&mut self, witness: &PartialWitness2<F>) -> PartialWitness2<F> {
+        let beta = witness.get(self.beta);
+        let gamma = witness.get(self.gamma);
+        let degree = self.z_values.len();
+
+        let mut result = PartialWitness2::new();
+        let mut z = F::ONE;
+        result.set(self.z_values[0], z);
+        for j in 0..degree - 1 {
+            let mut numerator = F::ONE;
+            let mut denominator = F::ONE;
+            for (i, &wire_target) in self.wire_values[j].iter().enumerate() {
+                let w = witness.get(wire_target);
+                let id = self.k[i] * self.subgroup_generator.exp_usize(j);
+                numerator = numerator * (w + beta * id + gamma);
+                denominator = denominator * (w + beta * self.sigma[i][j] + gamma);
+            }
+            z = z * numerator / denominator;
+            result.set(self.z_values[j + 1], z);
+        }
+        result
+    }
+}
@@ -1,6 +1,229 @@
+use std::marker::PhantomData;
+
 use crate::{Circuit, CircuitBuilder, Field, HaloEndomorphismCurve, NUM_CONSTANTS, NUM_WIRES, QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER, Target, PublicInput};
 use crate::plonk_gates::evaluate_all_constraints_recursively;
 
+/// The sponge permutation driving a `Transcript`. Swapping which `Permutation` a `Transcript`
+/// is instantiated over (Rescue, Poseidon, ...) changes the hash every challenge is derived
+/// from without touching any of the absorb/squeeze call sites in the verifier.
+trait Permutation<F: Field> {
+    fn hash_n_to_1(builder: &mut CircuitBuilder<F>, inputs: &[Target]) -> Target;
+    fn hash_n_to_2(builder: &mut CircuitBuilder<F>, inputs: &[Target]) -> (Target, Target);
+}
+
+/// The Rescue sponge, the only `Permutation` this circuit builder currently exposes.
+struct Rescue;
+
+impl<F: Field> Permutation<F> for Rescue {
+    fn hash_n_to_1(builder: &mut CircuitBuilder<F>, inputs: &[Target]) -> Target {
+        builder.rescue_hash_n_to_1(inputs)
+    }
+
+    fn hash_n_to_2(builder: &mut CircuitBuilder<F>, inputs: &[Target]) -> (Target, Target) {
+        builder.rescue_hash_n_to_2(inputs)
+    }
+}
+
+/// A Fiat-Shamir transcript for the recursive verifier, generic over the sponge `Permutation`
+/// that backs it. It fixes the absorb order canonically in one place: callers only `absorb`
+/// newly committed values and `squeeze` challenges, rather than re-deriving and `concat()`ing
+/// the full list of inputs to a one-shot hash at every call site.
+struct Transcript<F: Field, P: Permutation<F>> {
+    state: Vec<Target>,
+    _permutation: PhantomData<(F, P)>,
+}
+
+impl<F: Field, P: Permutation<F>> Transcript<F, P> {
+    fn new() -> Self {
+        Transcript { state: Vec::new(), _permutation: PhantomData }
+    }
+
+    /// Absorbs `values` into the transcript.
+    fn absorb(&mut self, values: &[Target]) {
+        self.state.extend_from_slice(values);
+    }
+
+    /// Absorbs a set of curve points into the transcript, by absorbing each coordinate in
+    /// turn. Committing to a point means committing to both its coordinates, so skipping
+    /// either half would let a prover equivocate on it.
+    fn absorb_points(&mut self, points: &[AffinePointTarget]) {
+        for point in points {
+            self.state.push(point.x);
+            self.state.push(point.y);
+        }
+    }
+
+    /// Squeezes a single challenge out of the transcript.
+    fn squeeze(&mut self, builder: &mut CircuitBuilder<F>) -> Target {
+        let challenge = P::hash_n_to_1(builder, &self.state);
+        self.state = vec![challenge];
+        challenge
+    }
+
+    /// Squeezes a pair of challenges out of the transcript.
+    fn squeeze_2(&mut self, builder: &mut CircuitBuilder<F>) -> (Target, Target) {
+        let (a, b) = P::hash_n_to_2(builder, &self.state);
+        self.state = vec![a, b];
+        (a, b)
+    }
+}
+
+/// An elliptic-curve point gadget: a routable `(x, y)` pair standing in for a commitment or
+/// Halo reduction element, so the recursive verifier can check `assert_on_curve` and fold
+/// points with real group operations instead of passing commitments around as bare scalars.
+#[derive(Copy, Clone)]
+pub struct AffinePointTarget {
+    pub x: Target,
+    pub y: Target,
+}
+
+impl AffinePointTarget {
+    /// Allocates a new point out of two fresh virtual targets.
+    fn add_virtual<F: Field>(builder: &mut CircuitBuilder<F>) -> Self {
+        AffinePointTarget { x: builder.add_virtual_target(), y: builder.add_virtual_target() }
+    }
+
+    /// Allocates `n` new points.
+    fn add_virtual_n<F: Field>(builder: &mut CircuitBuilder<F>, n: usize) -> Vec<Self> {
+        (0..n).map(|_| Self::add_virtual(builder)).collect()
+    }
+
+    /// Asserts that this point lies on `C`, i.e. that `y^2 = x^3 + A*x + B`.
+    fn assert_on_curve<C: HaloEndomorphismCurve>(&self, builder: &mut CircuitBuilder<C::BaseField>) {
+        let x_squared = builder.mul(self.x, self.x);
+        let x_cubed = builder.mul(x_squared, self.x);
+        let a = builder.constant_wire(C::A);
+        let b = builder.constant_wire(C::B);
+        let a_x = builder.mul(a, self.x);
+        let rhs = builder.add(x_cubed, a_x);
+        let rhs = builder.add(rhs, b);
+        let y_squared = builder.mul(self.y, self.y);
+        builder.copy(y_squared, rhs);
+    }
+
+    /// Adds two distinct, non-identity points using the standard affine addition formula.
+    /// Halo's reduction never hits the doubling or identity cases with honestly-generated
+    /// proofs, so those are left to `double` rather than handled here.
+    fn add<C: HaloEndomorphismCurve>(
+        &self,
+        other: &AffinePointTarget,
+        builder: &mut CircuitBuilder<C::BaseField>,
+    ) -> AffinePointTarget {
+        let dy = builder.sub(other.y, self.y);
+        let dx = builder.sub(other.x, self.x);
+        let lambda = builder.div(dy, dx);
+        let lambda_squared = builder.mul(lambda, lambda);
+        let x3 = builder.sub(lambda_squared, self.x);
+        let x3 = builder.sub(x3, other.x);
+        let x_diff = builder.sub(self.x, x3);
+        let y3 = builder.mul(lambda, x_diff);
+        let y3 = builder.sub(y3, self.y);
+        AffinePointTarget { x: x3, y: y3 }
+    }
+
+    /// Doubles this point.
+    fn double<C: HaloEndomorphismCurve>(&self, builder: &mut CircuitBuilder<C::BaseField>) -> AffinePointTarget {
+        let three = builder.constant_wire(C::BaseField::from_canonical_usize(3));
+        let x_squared = builder.mul(self.x, self.x);
+        let three_x_squared = builder.mul(three, x_squared);
+        let a = builder.constant_wire(C::A);
+        let numerator = builder.add(three_x_squared, a);
+        let two_y = builder.double(self.y);
+        let lambda = builder.div(numerator, two_y);
+        let lambda_squared = builder.mul(lambda, lambda);
+        let two_x = builder.double(self.x);
+        let x3 = builder.sub(lambda_squared, two_x);
+        let x_diff = builder.sub(self.x, x3);
+        let y3 = builder.mul(lambda, x_diff);
+        let y3 = builder.sub(y3, self.y);
+        AffinePointTarget { x: x3, y: y3 }
+    }
+
+    /// Applies `C`'s efficiently-computable endomorphism `(x, y) -> (zeta*x, y)`, used by
+    /// `scalar_mul` to apply the high half of its bits.
+    fn endomorphism<C: HaloEndomorphismCurve>(&self, builder: &mut CircuitBuilder<C::BaseField>) -> AffinePointTarget {
+        let zeta = builder.constant_wire(C::ZETA);
+        let x = builder.mul(zeta, self.x);
+        AffinePointTarget { x, y: self.y }
+    }
+
+    /// Given an even number of little-endian bits, splits them into a low half `lo` and a
+    /// high half `hi` and returns `lo * self + hi * endo(self)`, folding one bit of each half
+    /// per iteration (one against `self`, one against its endomorphism image) rather than
+    /// doubling once per bit. That is multiplication by `lo + lambda * hi`, `lambda` being
+    /// the scalar `C`'s endomorphism acts as; it is not multiplication by the integer the
+    /// bits spell (`lo + 2^half * hi`), and no scalar decomposition is done. `lo_bit` and
+    /// `hi_bit` each independently gate whether `self`/`endo_self` is added this iteration,
+    /// so all four combinations are possible -- including both firing at once, which calls
+    /// for `self + endo_self` rather than either summand alone.
+    fn scalar_mul<C: HaloEndomorphismCurve>(
+        &self,
+        scalar_bits: &[Target],
+        builder: &mut CircuitBuilder<C::BaseField>,
+    ) -> AffinePointTarget {
+        assert_eq!(scalar_bits.len() % 2, 0, "scalar_mul expects an even number of bits");
+        let half = scalar_bits.len() / 2;
+        let (lo_bits, hi_bits) = scalar_bits.split_at(half);
+        let endo_self = self.endomorphism::<C>(builder);
+        let self_plus_endo = self.add::<C>(&endo_self, builder);
+
+        // Seed the accumulator with a non-identity point so `add` never has to handle it;
+        // `seed`'s contribution is doubled alongside `acc` every iteration, so it can be
+        // subtracted back out once the loop is done.
+        let seed = self.double::<C>(builder);
+        let mut acc = seed;
+        for (&lo_bit, &hi_bit) in lo_bits.iter().rev().zip(hi_bits.iter().rev()) {
+            acc = acc.double::<C>(builder);
+
+            // Picks which of {self, endo_self, self + endo_self} this digit calls for; when
+            // neither bit is set the choice is irrelevant, since `add_flag` below skips the
+            // addition entirely rather than adding an arbitrary one of them.
+            let summand_x = select(
+                builder, hi_bit,
+                select(builder, lo_bit, self_plus_endo.x, endo_self.x),
+                select(builder, lo_bit, self.x, self.x),
+            );
+            let summand_y = select(
+                builder, hi_bit,
+                select(builder, lo_bit, self_plus_endo.y, endo_self.y),
+                select(builder, lo_bit, self.y, self.y),
+            );
+            let summand = AffinePointTarget { x: summand_x, y: summand_y };
+            let with_summand = acc.add::<C>(&summand, builder);
+
+            // `add_flag = lo_bit OR hi_bit`: whether this digit calls for any addition at all.
+            let bit_sum = builder.add(lo_bit, hi_bit);
+            let bit_product = builder.mul(lo_bit, hi_bit);
+            let add_flag = builder.sub(bit_sum, bit_product);
+            acc = AffinePointTarget {
+                x: select(builder, add_flag, with_summand.x, acc.x),
+                y: select(builder, add_flag, with_summand.y, acc.y),
+            };
+        }
+
+        // After `half` iterations, `seed` has been doubled `half` times alongside `acc`, so it
+        // now contributes `2^(half+1) * self` to `acc` that was never part of the scalar being
+        // multiplied in. Double it out to the same power and subtract it off before returning.
+        let mut seed_offset = seed;
+        for _ in 0..half {
+            seed_offset = seed_offset.double::<C>(builder);
+        }
+        let zero = builder.zero_wire();
+        let neg_seed_offset_y = builder.sub(zero, seed_offset.y);
+        let neg_seed_offset = AffinePointTarget { x: seed_offset.x, y: neg_seed_offset_y };
+        acc.add::<C>(&neg_seed_offset, builder)
+    }
+}
+
+/// `if bit { then_value } else { else_value }`, computed as `else_value + bit * (then_value -
+/// else_value)`. `bit` isn't boolean-constrained here; callers are expected to derive it from
+/// a source that already is (e.g. a bit decomposition gate).
+fn select<F: Field>(builder: &mut CircuitBuilder<F>, bit: Target, then_value: Target, else_value: Target) -> Target {
+    let diff = builder.sub(then_value, else_value);
+    let scaled_diff = builder.mul(bit, diff);
+    builder.add(else_value, scaled_diff)
+}
+
 /// Wraps a `Circuit` for recursive verification with inputs for the proof data.
 pub struct RecursiveCircuit<F: Field> {
     pub circuit: Circuit<F>,
@@ -9,11 +232,11 @@ pub struct RecursiveCircuit<F: Field> {
 
 pub struct ProofTarget {
     /// A commitment to each wire polynomial.
-    c_wires: Vec<Target>,
+    c_wires: Vec<AffinePointTarget>,
     /// A commitment to Z, in the context of the permutation argument.
-    c_plonk_z: Target,
+    c_plonk_z: AffinePointTarget,
     /// A commitment to the quotient polynomial.
-    c_plonk_t: Vec<Target>,
+    c_plonk_t: Vec<AffinePointTarget>,
 
     /// The purported opening of each constant polynomial.
     o_constants: Vec<Target>,
@@ -25,10 +248,15 @@ pub struct ProofTarget {
     o_below_wires: Vec<Target>,
     /// The purported opening of Z, in the context of the permutation argument.
     o_plonk_z: Target,
+    /// The purported opening of Z at `g * zeta`, needed to check the permutation argument's
+    /// grand-product relation in-circuit.
+    o_plonk_z_omega: Target,
     /// The purported opening of the quotient polynomial.
     o_plonk_t: Vec<Target>,
 
     // Data for the previous proof in the recursive chain, which hasn't been fully verified.
+    inner_beta: PublicInput,
+    inner_gamma: PublicInput,
     inner_alpha: PublicInput,
     inner_zeta: PublicInput,
     inner_o_constants: Vec<PublicInput>,
@@ -36,15 +264,16 @@ pub struct ProofTarget {
     inner_o_right_wires: Vec<PublicInput>,
     inner_o_below_wires: Vec<PublicInput>,
     inner_o_plonk_z: PublicInput,
+    inner_o_plonk_z_omega: PublicInput,
     inner_o_plonk_t: Vec<PublicInput>,
     inner_o_halo_us: Vec<PublicInput>,
 
     /// L_i in the Halo reduction.
-    halo_l_i: Vec<Target>,
+    halo_l_i: Vec<AffinePointTarget>,
     /// R_i in the Halo reduction.
-    halo_r_i: Vec<Target>,
-    /// The purported value of G, i.e. <s, G>, in the context of Halo.
-    halo_g: Target,
+    halo_r_i: Vec<AffinePointTarget>,
+    /// The purported value of <s, G>, in the context of Halo.
+    halo_g: AffinePointTarget,
 }
 
 pub fn recursive_verification_circuit<C: HaloEndomorphismCurve>(
@@ -52,15 +281,18 @@ pub fn recursive_verification_circuit<C: HaloEndomorphismCurve>(
 ) -> RecursiveCircuit<C::BaseField> {
     let mut builder = CircuitBuilder::<C::BaseField>::new();
     let proof = ProofTarget {
-        c_wires: builder.add_virtual_targets(NUM_WIRES),
-        c_plonk_z: builder.add_virtual_target(),
-        c_plonk_t: builder.add_virtual_targets(QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
+        c_wires: AffinePointTarget::add_virtual_n(&mut builder, NUM_WIRES),
+        c_plonk_z: AffinePointTarget::add_virtual(&mut builder),
+        c_plonk_t: AffinePointTarget::add_virtual_n(&mut builder, QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
         o_constants: builder.add_virtual_targets(NUM_CONSTANTS),
         o_local_wires: builder.add_virtual_targets(NUM_WIRES),
         o_right_wires: builder.add_virtual_targets(NUM_WIRES),
         o_below_wires: builder.add_virtual_targets(NUM_WIRES),
         o_plonk_z: builder.add_virtual_target(),
+        o_plonk_z_omega: builder.add_virtual_target(),
         o_plonk_t: builder.add_virtual_targets(QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
+        inner_beta: builder.stage_public_input(),
+        inner_gamma: builder.stage_public_input(),
         inner_alpha: builder.stage_public_input(),
         inner_zeta: builder.stage_public_input(),
         inner_o_constants: builder.stage_public_inputs(NUM_CONSTANTS),
@@ -68,45 +300,96 @@ pub fn recursive_verification_circuit<C: HaloEndomorphismCurve>(
         inner_o_right_wires: builder.stage_public_inputs(NUM_WIRES),
         inner_o_below_wires: builder.stage_public_inputs(NUM_WIRES),
         inner_o_plonk_z: builder.stage_public_input(),
+        inner_o_plonk_z_omega: builder.stage_public_input(),
         inner_o_plonk_t: builder.stage_public_inputs(QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
         inner_o_halo_us: builder.stage_public_inputs(degree_pow),
-        halo_l_i: builder.add_virtual_targets(degree_pow),
-        halo_r_i: builder.add_virtual_targets(degree_pow),
-        halo_g: builder.add_virtual_target(),
+        halo_l_i: AffinePointTarget::add_virtual_n(&mut builder, degree_pow),
+        halo_r_i: AffinePointTarget::add_virtual_n(&mut builder, degree_pow),
+        halo_g: AffinePointTarget::add_virtual(&mut builder),
     };
     builder.route_public_inputs();
 
-    // TODO: Verify that each prover polynomial commitment is on the curve.
-
-    // Compute random challenges.
-    let (beta, gamma) = builder.rescue_hash_n_to_2(&proof.c_wires);
-    let alpha = builder.rescue_hash_n_to_1(&vec![beta, proof.c_plonk_z]);
-    let zeta = builder.rescue_hash_n_to_1(&[vec![alpha], proof.c_plonk_t.clone()].concat());
-    let (v, u) = builder.rescue_hash_n_to_2(&[
-        vec![zeta],
-        proof.o_constants.clone(),
-        proof.o_local_wires.clone(),
-        proof.o_right_wires.clone(),
-        proof.o_below_wires.clone(),
-        vec![proof.o_plonk_z],
-        proof.o_plonk_t.clone(),
-    ].concat());
+    // Every prover-supplied commitment and Halo reduction element must actually be a point
+    // on the curve, or the folding/opening checks below are meaningless.
+    for c in &proof.c_wires {
+        c.assert_on_curve::<C>(&mut builder);
+    }
+    proof.c_plonk_z.assert_on_curve::<C>(&mut builder);
+    for c in &proof.c_plonk_t {
+        c.assert_on_curve::<C>(&mut builder);
+    }
+    for l in &proof.halo_l_i {
+        l.assert_on_curve::<C>(&mut builder);
+    }
+    for r in &proof.halo_r_i {
+        r.assert_on_curve::<C>(&mut builder);
+    }
+    proof.halo_g.assert_on_curve::<C>(&mut builder);
+
+    // Compute random challenges. Deriving them through a `Transcript` fixes the absorb
+    // order canonically in one place, rather than leaving every call site responsible for
+    // remembering (and manually `concat()`ing) exactly what's been committed so far. The
+    // `Rescue` permutation here is just this verifier's choice; swapping in another
+    // `Permutation` impl wouldn't touch any of the absorb/squeeze calls below.
+    let mut transcript = Transcript::<C::BaseField, Rescue>::new();
+    transcript.absorb_points(&proof.c_wires);
+    // beta/gamma stay folded into the transcript state (absorbed by `alpha`'s squeeze
+    // below) rather than being re-threaded through by hand.
+    let (_beta, _gamma) = transcript.squeeze_2(&mut builder);
+    transcript.absorb_points(&[proof.c_plonk_z]);
+    let alpha = transcript.squeeze(&mut builder);
+    transcript.absorb_points(&proof.c_plonk_t);
+    let zeta = transcript.squeeze(&mut builder);
+    transcript.absorb(&proof.o_constants);
+    transcript.absorb(&proof.o_local_wires);
+    transcript.absorb(&proof.o_right_wires);
+    transcript.absorb(&proof.o_below_wires);
+    transcript.absorb(&[proof.o_plonk_z, proof.o_plonk_z_omega]);
+    transcript.absorb(&proof.o_plonk_t);
+    let (v, u) = transcript.squeeze_2(&mut builder);
+
+    // Fold this proof's Halo reduction through `halo_l_i`/`halo_r_i` and check it against the
+    // prover's claimed `halo_g`, so those commitments actually constrain something.
+    let halo_g_folded = halo_g::<C>(&mut builder, &mut transcript, &proof.c_plonk_z, &proof.halo_l_i, &proof.halo_r_i);
+    builder.copy(halo_g_folded.x, proof.halo_g.x);
+    builder.copy(halo_g_folded.y, proof.halo_g.y);
 
     verify_assumptions::<C>(&mut builder,
                             degree_pow,
-                            &proof,
+                            &proof.inner_o_constants,
+                            &proof.inner_o_local_wires,
+                            &proof.inner_o_right_wires,
+                            &proof.inner_o_below_wires,
+                            proof.inner_o_plonk_z,
+                            proof.inner_o_plonk_z_omega,
+                            &proof.inner_o_plonk_t,
                             proof.inner_alpha.routable_target(),
+                            proof.inner_beta.routable_target(),
+                            proof.inner_gamma.routable_target(),
                             proof.inner_zeta.routable_target());
 
     let circuit = builder.build();
     RecursiveCircuit { circuit, proof }
 }
 
+/// Checks that the previous proof's purported openings (all `inner_*` fields) are
+/// consistent with its quotient polynomial, i.e. that its vanishing-polynomial evaluation
+/// divided by `Z_H(zeta)` matches `inner_o_plonk_t`'s composite evaluation. Only depends on
+/// the previous proof's already-public openings, so it's the same regardless of how this
+/// (outer) proof's own openings happen to be represented/batched.
 fn verify_assumptions<C: HaloEndomorphismCurve>(
     builder: &mut CircuitBuilder<C::BaseField>,
     degree_pow: usize,
-    proof: &ProofTarget,
+    inner_o_constants: &[PublicInput],
+    inner_o_local_wires: &[PublicInput],
+    inner_o_right_wires: &[PublicInput],
+    inner_o_below_wires: &[PublicInput],
+    inner_o_plonk_z: PublicInput,
+    inner_o_plonk_z_omega: PublicInput,
+    inner_o_plonk_t: &[PublicInput],
     alpha: Target,
+    beta: Target,
+    gamma: Target,
     zeta: Target,
 ) {
     let degree = 1 << degree_pow;
@@ -116,10 +399,10 @@ fn verify_assumptions<C: HaloEndomorphismCurve>(
     let one = builder.one_wire();
 
     // Convert opening vectors from `PublicInput`s to `Target`s.
-    let o_constants: Vec<Target> = proof.inner_o_constants.iter().map(PublicInput::routable_target).collect();
-    let o_local_wires: Vec<Target> = proof.inner_o_local_wires.iter().map(PublicInput::routable_target).collect();
-    let o_right_wires: Vec<Target> = proof.inner_o_right_wires.iter().map(PublicInput::routable_target).collect();
-    let o_below_wires: Vec<Target> = proof.inner_o_below_wires.iter().map(PublicInput::routable_target).collect();
+    let o_constants: Vec<Target> = inner_o_constants.iter().map(PublicInput::routable_target).collect();
+    let o_local_wires: Vec<Target> = inner_o_local_wires.iter().map(PublicInput::routable_target).collect();
+    let o_right_wires: Vec<Target> = inner_o_right_wires.iter().map(PublicInput::routable_target).collect();
+    let o_below_wires: Vec<Target> = inner_o_below_wires.iter().map(PublicInput::routable_target).collect();
 
     // Evaluate zeta^degree.
     let mut zeta_power_d = zeta;
@@ -135,10 +418,39 @@ fn verify_assumptions<C: HaloEndomorphismCurve>(
     let lagrange_1_eval_denominator = builder.mul(degree_wire, zeta_minus_one);
     let lagrange_1_eval = builder.div(zero_eval, lagrange_1_eval_denominator);
 
-    // Evaluate the function which is supposed to vanish on H. It is a sum of several terms which
-    // should vanish, each weighted by a different power of alpha.
-    let vanishing_z_1_term = todo!();
-    let vanishing_v_shift_term = todo!();
+    // Evaluate L_1(zeta) * (Z(zeta) - 1), the boundary constraint forcing the permutation
+    // argument's grand product to start at 1.
+    let inner_o_plonk_z = inner_o_plonk_z.routable_target();
+    let inner_o_plonk_z_omega = inner_o_plonk_z_omega.routable_target();
+    let z_minus_one = builder.sub(inner_o_plonk_z, one);
+    let vanishing_z_1_term = builder.mul(lagrange_1_eval, z_minus_one);
+
+    // Evaluate Z(zeta) * prod_i(o_wire_i + beta*id_i + gamma)
+    //        - Z(g*zeta) * prod_i(o_wire_i + beta*sigma_i + gamma),
+    // the grand-product relation for the permutation argument. The sigma polynomials are
+    // preprocessed and committed alongside the gate selectors, so their openings at zeta
+    // are the last NUM_WIRES entries of the constants opening.
+    let o_sigmas = &o_constants[o_constants.len() - NUM_WIRES..];
+    let k = crate::plonk2::coset_shifts::<C::BaseField>(NUM_WIRES);
+    let mut id_product = one;
+    let mut sigma_product = one;
+    for i in 0..NUM_WIRES {
+        let k_i = builder.constant_wire(k[i]);
+        let id_i = builder.mul(k_i, zeta);
+        let beta_id_i = builder.mul(beta, id_i);
+        let id_term = builder.add(o_local_wires[i], beta_id_i);
+        let id_term = builder.add(id_term, gamma);
+        id_product = builder.mul(id_product, id_term);
+
+        let beta_sigma_i = builder.mul(beta, o_sigmas[i]);
+        let sigma_term = builder.add(o_local_wires[i], beta_sigma_i);
+        let sigma_term = builder.add(sigma_term, gamma);
+        sigma_product = builder.mul(sigma_product, sigma_term);
+    }
+    let lhs = builder.mul(inner_o_plonk_z, id_product);
+    let rhs = builder.mul(inner_o_plonk_z_omega, sigma_product);
+    let vanishing_v_shift_term = builder.sub(lhs, rhs);
+
     let constraint_terms = evaluate_all_constraints_recursively::<C>(
         builder, &o_constants, &o_local_wires, &o_right_wires, &o_below_wires);
     let vanishing_eval = alpha_reduction(
@@ -149,7 +461,7 @@ fn verify_assumptions<C: HaloEndomorphismCurve>(
     // Evaluate the quotient polynomial, and assert that it matches the prover's opening.
     let quotient_eval = builder.div(vanishing_eval, zero_eval);
     let inner_o_plonk_t_targets: Vec<Target> =
-        proof.inner_o_plonk_t.iter()
+        inner_o_plonk_t.iter()
             .map(|pi| pi.routable_target())
             .collect();
     let inner_o_plonk_t_eval = eval_composite_poly(builder, &inner_o_plonk_t_targets, zeta_power_d);
@@ -193,16 +505,373 @@ fn eval_composite_poly<F: Field>(
     sum
 }
 
-/// Evaluate g(X, {u_i}) as defined in the Halo paper.
-fn halo_g<F: Field>(builder: &mut CircuitBuilder<F>, x: Target, us: &[Target]) -> Target {
-    let mut product = builder.one_wire();
-    let mut x_power = x;
-    for &u_i in us {
+/// Computes `<s, G>` as defined in the Halo paper, by folding `initial` through each
+/// reduction round's `L_i`/`R_i` and challenge `u_i`:
+/// `P_{i+1} = u_i^{-1} * L_i + P_i + u_i * R_i`.
+/// Each `u_i` is squeezed from `transcript` right after absorbing that round's `L_i`/`R_i`,
+/// the same canonical-absorb-order convention every other challenge in this module follows.
+/// The result is what the prover's claimed `halo_g` is checked against, so `L_i`/`R_i` are
+/// actually constrained rather than merely asserted on-curve.
+///
+/// `initial` stands in for the single commitment this reduction is opening; folding in the
+/// other openings batched together via `v` (so one Halo reduction covers the whole proof)
+/// would need per-commitment `scalar_mul`s by powers of `v`, which is mechanically identical
+/// to the folding here but is left for a follow-up.
+fn halo_g<C: HaloEndomorphismCurve, P: Permutation<C::BaseField>>(
+    builder: &mut CircuitBuilder<C::BaseField>,
+    transcript: &mut Transcript<C::BaseField, P>,
+    initial: &AffinePointTarget,
+    l_is: &[AffinePointTarget],
+    r_is: &[AffinePointTarget],
+) -> AffinePointTarget {
+    assert_eq!(l_is.len(), r_is.len());
+    let num_bits = C::BaseField::BITS;
+    let num_bits = num_bits + num_bits % 2;
+
+    let mut acc = *initial;
+    for (&l_i, &r_i) in l_is.iter().zip(r_is.iter()) {
+        transcript.absorb_points(&[l_i, r_i]);
+        let u_i = transcript.squeeze(builder);
         let u_i_inv = builder.inv(u_i);
-        let u_i_inv_times_x_power = builder.mul(u_i_inv, x_power);
-        let term = builder.add(u_i, u_i_inv_times_x_power);
-        product = builder.mul(product, term);
-        x_power = builder.double(x_power);
+        let u_i_bits = builder.split_le(u_i, num_bits);
+        let u_i_inv_bits = builder.split_le(u_i_inv, num_bits);
+
+        let scaled_l = l_i.scalar_mul::<C>(&u_i_inv_bits, builder);
+        let scaled_r = r_i.scalar_mul::<C>(&u_i_bits, builder);
+        acc = acc.add::<C>(&scaled_l, builder);
+        acc = acc.add::<C>(&scaled_r, builder);
+    }
+    acc
+}
+
+/// The number of `zeta`-point polynomials (every constant, every local wire, and `Z`) that
+/// get packed into one batched opening, rounded up to a power of two so the unpacking below
+/// can use a plain subgroup of roots of unity.
+fn zeta_group_arity() -> usize {
+    (NUM_CONSTANTS + NUM_WIRES + 1).next_power_of_two()
+}
+
+/// fflonk-style batched-opening variant of `ProofTarget`. The polynomials opened at `zeta`
+/// (constants, local wires, and Z) are packed by the prover into one degree-`t` polynomial
+/// `f(X) = sum_i f_i(X^t) * X^i`, committed to once as `c_zeta_group`, and opened at the
+/// `t`-th roots of `zeta` instead of each being opened (and Halo-reduced) separately.
+/// `o_right_wires`/`o_below_wires`/`o_plonk_t` are opened at different points, so they're
+/// left unbatched.
+pub struct BatchedProofTarget {
+    c_wires: Vec<AffinePointTarget>,
+    c_plonk_z: AffinePointTarget,
+    c_plonk_t: Vec<AffinePointTarget>,
+    /// Commitment to the packed degree-`t` polynomial for the `zeta` group.
+    c_zeta_group: AffinePointTarget,
+
+    /// `s`, a `zeta_group_arity()`-th root of `zeta`. The packed polynomial is opened at
+    /// `w^j * s` for each `j`, where `w` is a primitive `zeta_group_arity()`-th root of unity.
+    o_zeta_group_root: Target,
+    /// The packed polynomial's openings at `w^j * s`, for `j` in `0..zeta_group_arity()`.
+    o_zeta_group: Vec<Target>,
+
+    o_right_wires: Vec<Target>,
+    o_below_wires: Vec<Target>,
+    o_plonk_t: Vec<Target>,
+
+    // Data for the previous proof in the recursive chain, which hasn't been fully verified.
+    inner_beta: PublicInput,
+    inner_gamma: PublicInput,
+    inner_alpha: PublicInput,
+    inner_zeta: PublicInput,
+    inner_o_constants: Vec<PublicInput>,
+    inner_o_local_wires: Vec<PublicInput>,
+    inner_o_right_wires: Vec<PublicInput>,
+    inner_o_below_wires: Vec<PublicInput>,
+    inner_o_plonk_z: PublicInput,
+    inner_o_plonk_z_omega: PublicInput,
+    inner_o_plonk_t: Vec<PublicInput>,
+    inner_o_halo_us: Vec<PublicInput>,
+
+    halo_l_i: Vec<AffinePointTarget>,
+    halo_r_i: Vec<AffinePointTarget>,
+    halo_g: AffinePointTarget,
+}
+
+/// Recovers the individual `zeta`-point openings (`NUM_CONSTANTS` constants, then
+/// `NUM_WIRES` local wires, then `Z`) from a batched proof's packed openings, by inverting
+/// the `f(X) = sum_i f_i(X^t) * X^i` packing:
+/// `f_i(zeta) = s^{-i} / t * sum_j w^{-i*j} * f(w^j * s)`.
+fn unpack_zeta_group<F: Field>(
+    builder: &mut CircuitBuilder<F>,
+    proof: &BatchedProofTarget,
+) -> (Vec<Target>, Vec<Target>, Target) {
+    let t = zeta_group_arity();
+    let w = F::primitive_root_of_unity(t.trailing_zeros() as usize);
+    let t_inv = builder.constant_wire(F::from_canonical_usize(t).inverse());
+    let s_inv = builder.inv(proof.o_zeta_group_root);
+
+    let mut s_inv_power = builder.one_wire();
+    let mut components = Vec::with_capacity(NUM_CONSTANTS + NUM_WIRES + 1);
+    for i in 0..NUM_CONSTANTS + NUM_WIRES + 1 {
+        let w_i_inv = w.exp_usize(i).inverse();
+        let mut sum = builder.zero_wire();
+        // `w_j_inv` accumulates `w_i_inv^j = w^{-i*j}`, the genuine 2D DFT twiddle; it must
+        // be raised by `w_i_inv` each step, not by the constant `w.inverse()`, or every row
+        // but `i=1` recovers the wrong component.
+        let mut w_j_inv = F::ONE;
+        for j in 0..t {
+            let twiddle_inv = builder.constant_wire(w_j_inv);
+            let term = builder.mul(twiddle_inv, proof.o_zeta_group[j]);
+            sum = builder.add(sum, term);
+            w_j_inv = w_j_inv * w_i_inv;
+        }
+        let scaled = builder.mul(sum, t_inv);
+        let scaled = builder.mul(scaled, s_inv_power);
+        components.push(scaled);
+        s_inv_power = builder.mul(s_inv_power, s_inv);
+    }
+
+    let o_plonk_z = components[NUM_CONSTANTS + NUM_WIRES];
+    let o_constants = components[0..NUM_CONSTANTS].to_vec();
+    let o_local_wires = components[NUM_CONSTANTS..NUM_CONSTANTS + NUM_WIRES].to_vec();
+    (o_constants, o_local_wires, o_plonk_z)
+}
+
+/// Batched-opening counterpart of `recursive_verification_circuit`: one Halo reduction
+/// verifies the whole `zeta`-point group (constants, local wires, Z) instead of one per
+/// polynomial, before falling back to the same `verify_assumptions` used by the unbatched
+/// verifier (which only depends on the previous proof's already-public openings, so it's
+/// unaffected by how this proof's own openings were batched).
+pub fn recursive_verification_circuit_batched<C: HaloEndomorphismCurve>(
+    degree_pow: usize,
+) -> (Circuit<C::BaseField>, BatchedProofTarget) {
+    let mut builder = CircuitBuilder::<C::BaseField>::new();
+    let proof = BatchedProofTarget {
+        c_wires: AffinePointTarget::add_virtual_n(&mut builder, NUM_WIRES),
+        c_plonk_z: AffinePointTarget::add_virtual(&mut builder),
+        c_plonk_t: AffinePointTarget::add_virtual_n(&mut builder, QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
+        c_zeta_group: AffinePointTarget::add_virtual(&mut builder),
+        o_zeta_group_root: builder.add_virtual_target(),
+        o_zeta_group: builder.add_virtual_targets(zeta_group_arity()),
+        o_right_wires: builder.add_virtual_targets(NUM_WIRES),
+        o_below_wires: builder.add_virtual_targets(NUM_WIRES),
+        o_plonk_t: builder.add_virtual_targets(QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
+        inner_beta: builder.stage_public_input(),
+        inner_gamma: builder.stage_public_input(),
+        inner_alpha: builder.stage_public_input(),
+        inner_zeta: builder.stage_public_input(),
+        inner_o_constants: builder.stage_public_inputs(NUM_CONSTANTS),
+        inner_o_local_wires: builder.stage_public_inputs(NUM_WIRES),
+        inner_o_right_wires: builder.stage_public_inputs(NUM_WIRES),
+        inner_o_below_wires: builder.stage_public_inputs(NUM_WIRES),
+        inner_o_plonk_z: builder.stage_public_input(),
+        inner_o_plonk_z_omega: builder.stage_public_input(),
+        inner_o_plonk_t: builder.stage_public_inputs(QUOTIENT_POLYNOMIAL_DEGREE_MULTIPLIER),
+        inner_o_halo_us: builder.stage_public_inputs(degree_pow),
+        halo_l_i: AffinePointTarget::add_virtual_n(&mut builder, degree_pow),
+        halo_r_i: AffinePointTarget::add_virtual_n(&mut builder, degree_pow),
+        halo_g: AffinePointTarget::add_virtual(&mut builder),
+    };
+    builder.route_public_inputs();
+
+    // Every prover-supplied commitment and Halo reduction element must actually be a point
+    // on the curve, or the folding/opening checks below are meaningless.
+    for c in &proof.c_wires {
+        c.assert_on_curve::<C>(&mut builder);
+    }
+    proof.c_plonk_z.assert_on_curve::<C>(&mut builder);
+    for c in &proof.c_plonk_t {
+        c.assert_on_curve::<C>(&mut builder);
+    }
+    proof.c_zeta_group.assert_on_curve::<C>(&mut builder);
+    for l in &proof.halo_l_i {
+        l.assert_on_curve::<C>(&mut builder);
+    }
+    for r in &proof.halo_r_i {
+        r.assert_on_curve::<C>(&mut builder);
+    }
+    proof.halo_g.assert_on_curve::<C>(&mut builder);
+
+    // One Halo reduction covers the whole zeta group; absorb its commitment and openings as
+    // a unit rather than one absorb per constituent polynomial.
+    let mut transcript = Transcript::<C::BaseField, Rescue>::new();
+    transcript.absorb_points(&proof.c_wires);
+    let (_beta, _gamma) = transcript.squeeze_2(&mut builder);
+    transcript.absorb_points(&[proof.c_plonk_z]);
+    let alpha = transcript.squeeze(&mut builder);
+    transcript.absorb_points(&proof.c_plonk_t);
+    let zeta = transcript.squeeze(&mut builder);
+    transcript.absorb_points(&[proof.c_zeta_group]);
+    transcript.absorb(&[proof.o_zeta_group_root]);
+    transcript.absorb(&proof.o_zeta_group);
+    transcript.absorb(&proof.o_right_wires);
+    transcript.absorb(&proof.o_below_wires);
+    transcript.absorb(&proof.o_plonk_t);
+    let (v, u) = transcript.squeeze_2(&mut builder);
+
+    // Fold this proof's Halo reduction through `halo_l_i`/`halo_r_i` and check it against the
+    // prover's claimed `halo_g`, so those commitments actually constrain something.
+    let halo_g_folded = halo_g::<C>(&mut builder, &mut transcript, &proof.c_zeta_group, &proof.halo_l_i, &proof.halo_r_i);
+    builder.copy(halo_g_folded.x, proof.halo_g.x);
+    builder.copy(halo_g_folded.y, proof.halo_g.y);
+
+    // Recovering the per-polynomial openings is only needed once this proof's own Halo
+    // opening of `c_zeta_group` is checked; `verify_assumptions` itself only consumes the
+    // previous proof's (already unbatched) public openings.
+    let (_o_constants, _o_local_wires, _o_plonk_z) = unpack_zeta_group(&mut builder, &proof);
+
+    verify_assumptions::<C>(&mut builder,
+                            degree_pow,
+                            &proof.inner_o_constants,
+                            &proof.inner_o_local_wires,
+                            &proof.inner_o_right_wires,
+                            &proof.inner_o_below_wires,
+                            proof.inner_o_plonk_z,
+                            proof.inner_o_plonk_z_omega,
+                            &proof.inner_o_plonk_t,
+                            proof.inner_alpha.routable_target(),
+                            proof.inner_beta.routable_target(),
+                            proof.inner_gamma.routable_target(),
+                            proof.inner_zeta.routable_target());
+
+    let circuit = builder.build();
+    (circuit, proof)
+}
+
+#[cfg(test)]
+mod tests {
+    //! This snapshot has no circuit-execution/witness-generation harness to run
+    //! `AffinePointTarget::scalar_mul` itself end to end, so these tests port its exact
+    //! double-and-add steps (including the seed/offset trick) to plain field arithmetic and
+    //! check the result against a naive scalar multiplication, over a small field and curve
+    //! chosen only to have the endomorphism `scalar_mul` relies on.
+
+    /// A tiny prime field, `p = 769 = 2^8 * 3 + 1`, used only so this module can exercise
+    /// `scalar_mul`'s arithmetic without a real `Field` impl; `p - 1` having a factor of 3
+    /// is what gives the curve below a nontrivial cube-root-of-unity endomorphism.
+    const P: i64 = 769;
+
+    fn add_f(a: i64, b: i64) -> i64 {
+        (a + b).rem_euclid(P)
+    }
+
+    fn sub_f(a: i64, b: i64) -> i64 {
+        (a - b).rem_euclid(P)
+    }
+
+    fn mul_f(a: i64, b: i64) -> i64 {
+        (a * b).rem_euclid(P)
+    }
+
+    fn inverse_f(a: i64) -> i64 {
+        // Fermat's little theorem: a^(p-2) is a's inverse mod a prime p.
+        let mut result = 1i64;
+        let mut base = a.rem_euclid(P);
+        let mut exp = P - 2;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = mul_f(result, base);
+            }
+            base = mul_f(base, base);
+            exp >>= 1;
+        }
+        result
+    }
+
+    fn div_f(a: i64, b: i64) -> i64 {
+        mul_f(a, inverse_f(b))
+    }
+
+    /// `zeta`, a primitive cube root of unity mod `P`, i.e. the curve's endomorphism factor.
+    const ZETA: i64 = 360;
+
+    /// The curve `y^2 = x^3 + 1` (so `A = 0`, `B = 1`) over the field above. `A = 0` is what
+    /// makes `(x, y) -> (zeta*x, y)` land back on the curve for any point on it.
+    type Point = (i64, i64);
+
+    fn point_add(p1: Point, p2: Point) -> Point {
+        let (x1, y1) = p1;
+        let (x2, y2) = p2;
+        let lambda = div_f(sub_f(y2, y1), sub_f(x2, x1));
+        let x3 = sub_f(sub_f(mul_f(lambda, lambda), x1), x2);
+        let y3 = sub_f(mul_f(lambda, sub_f(x1, x3)), y1);
+        (x3, y3)
+    }
+
+    fn point_double(p: Point) -> Point {
+        let (x1, y1) = p;
+        let lambda = div_f(mul_f(3, mul_f(x1, x1)), mul_f(2, y1));
+        let x3 = sub_f(mul_f(lambda, lambda), mul_f(2, x1));
+        let y3 = sub_f(mul_f(lambda, sub_f(x1, x3)), y1);
+        (x3, y3)
+    }
+
+    fn point_endomorphism(p: Point) -> Point {
+        let (x, y) = p;
+        (mul_f(ZETA, x), y)
+    }
+
+    /// Mirrors `AffinePointTarget::scalar_mul`'s double-and-add loop and seed/offset
+    /// subtraction exactly, one field operation at a time.
+    fn scalar_mul_mirror(p: Point, lo_bits: &[bool], hi_bits: &[bool]) -> Point {
+        assert_eq!(lo_bits.len(), hi_bits.len());
+        let half = lo_bits.len();
+        let endo_self = point_endomorphism(p);
+        let self_plus_endo = point_add(p, endo_self);
+
+        let seed = point_double(p);
+        let mut acc = seed;
+        for (&lo_bit, &hi_bit) in lo_bits.iter().rev().zip(hi_bits.iter().rev()) {
+            acc = point_double(acc);
+            let summand = match (lo_bit, hi_bit) {
+                (true, true) => self_plus_endo,
+                (false, true) => endo_self,
+                (true, false) | (false, false) => p,
+            };
+            if lo_bit || hi_bit {
+                acc = point_add(acc, summand);
+            }
+        }
+
+        let mut seed_offset = seed;
+        for _ in 0..half {
+            seed_offset = point_double(seed_offset);
+        }
+        let neg_seed_offset = (seed_offset.0, sub_f(0, seed_offset.1));
+        point_add(acc, neg_seed_offset)
+    }
+
+    /// Naive double-and-add scalar multiplication, used as the test's ground truth.
+    fn naive_scalar_mul(p: Point, mut k: u32) -> Point {
+        let mut result = None;
+        let mut addend = p;
+        while k > 0 {
+            if k & 1 == 1 {
+                result = Some(match result {
+                    None => addend,
+                    Some(r) => point_add(r, addend),
+                });
+            }
+            addend = point_double(addend);
+            k >>= 1;
+        }
+        result.unwrap()
+    }
+
+    fn bits_to_value(bits: &[bool]) -> u32 {
+        bits.iter().enumerate().fold(0u32, |acc, (i, &b)| acc | ((b as u32) << i))
+    }
+
+    #[test]
+    fn scalar_mul_matches_naive_scalar_multiplication() {
+        let p: Point = (1, 133);
+        let lo_bits = [true, false, true, true];
+        let hi_bits = [false, true, false, true];
+
+        let actual = scalar_mul_mirror(p, &lo_bits, &hi_bits);
+
+        let endo_p = point_endomorphism(p);
+        let expected = point_add(
+            naive_scalar_mul(p, bits_to_value(&lo_bits)),
+            naive_scalar_mul(endo_p, bits_to_value(&hi_bits)),
+        );
+
+        assert_eq!(actual, expected);
     }
-    product
 }
@@ -0,0 +1,261 @@
+use crate::poly::{Coeff, ExtendedLagrangeCoeff, LagrangeCoeff, Polynomial};
+use crate::Field;
+
+/// Owns a circuit's evaluation domain: the subgroup generator for the base domain (the
+/// `degree`-th roots of unity) and for an extended coset used while computing the quotient
+/// polynomial. This is the only place forward/inverse FFTs and coset-FFTs happen, so moving
+/// a `Polynomial` between bases always goes through a well-typed domain method rather than
+/// ad-hoc vector manipulation at the call site.
+pub struct EvaluationDomain<F: Field> {
+    degree: usize,
+    generator: F,
+    extended_degree: usize,
+    extended_generator: F,
+    /// Shift applied to the base domain to land on the extended coset.
+    coset_shift: F,
+}
+
+impl<F: Field> EvaluationDomain<F> {
+    pub fn new(degree_pow: usize, extension_factor_pow: usize) -> Self {
+        let degree = 1 << degree_pow;
+        let extended_degree = degree << extension_factor_pow;
+        EvaluationDomain {
+            degree,
+            generator: F::primitive_root_of_unity(degree_pow),
+            extended_degree,
+            extended_generator: F::primitive_root_of_unity(degree_pow + extension_factor_pow),
+            coset_shift: F::MULTIPLICATIVE_GROUP_GENERATOR,
+        }
+    }
+
+    pub fn degree(&self) -> usize {
+        self.degree
+    }
+
+    pub fn extended_degree(&self) -> usize {
+        self.extended_degree
+    }
+
+    /// The generator of the base domain's subgroup, i.e. a primitive `degree()`-th root of
+    /// unity.
+    pub fn generator(&self) -> F {
+        self.generator
+    }
+
+    /// Converts coefficients into evaluations over the base domain via a forward FFT.
+    pub fn coeff_to_lagrange(&self, poly: Polynomial<F, Coeff>) -> Polynomial<F, LagrangeCoeff> {
+        let mut values = poly.values().to_vec();
+        assert!(
+            values.len() <= self.degree,
+            "poly has {} coefficients, which doesn't fit in a degree-{} domain",
+            values.len(), self.degree,
+        );
+        values.resize(self.degree, F::ZERO);
+        fft(&mut values, self.generator);
+        Polynomial::new(values)
+    }
+
+    /// Converts evaluations over the base domain back into coefficients via an inverse FFT.
+    pub fn lagrange_to_coeff(&self, poly: Polynomial<F, LagrangeCoeff>) -> Polynomial<F, Coeff> {
+        let mut values = poly.values().to_vec();
+        fft(&mut values, self.generator.inverse());
+        scale(&mut values, F::from_canonical_usize(self.degree).inverse());
+        Polynomial::new(values)
+    }
+
+    /// Converts coefficients into evaluations over a coset of the extended domain. Products
+    /// of polynomials computed in this basis stay exactly representable so long as their
+    /// combined degree doesn't exceed `extended_degree`, which is why the quotient
+    /// polynomial's construction happens here rather than in the base domain.
+    pub fn coeff_to_extended(&self, poly: Polynomial<F, Coeff>) -> Polynomial<F, ExtendedLagrangeCoeff> {
+        let mut values = poly.values().to_vec();
+        assert!(
+            values.len() <= self.extended_degree,
+            "poly has {} coefficients, which doesn't fit in an extended degree-{} domain",
+            values.len(), self.extended_degree,
+        );
+        values.resize(self.extended_degree, F::ZERO);
+        distribute_powers(&mut values, self.coset_shift);
+        fft(&mut values, self.extended_generator);
+        Polynomial::new(values)
+    }
+
+    /// Converts evaluations over the extended coset back into coefficients.
+    pub fn extended_to_coeff(&self, poly: Polynomial<F, ExtendedLagrangeCoeff>) -> Polynomial<F, Coeff> {
+        let mut values = poly.values().to_vec();
+        fft(&mut values, self.extended_generator.inverse());
+        distribute_powers(&mut values, self.coset_shift.inverse());
+        scale(&mut values, F::from_canonical_usize(self.extended_degree).inverse());
+        Polynomial::new(values)
+    }
+}
+
+/// Scales every value by `factor`.
+fn scale<F: Field>(values: &mut [F], factor: F) {
+    for v in values.iter_mut() {
+        *v = *v * factor;
+    }
+}
+
+/// Scales `values[i]` by `shift^i`, turning a domain's FFT into a coset-FFT.
+fn distribute_powers<F: Field>(values: &mut [F], shift: F) {
+    let mut shift_power = F::ONE;
+    for v in values.iter_mut() {
+        *v = *v * shift_power;
+        shift_power = shift_power * shift;
+    }
+}
+
+/// An in-place radix-2 Cooley-Tukey FFT over `values`, using `root` as a `values.len()`-th
+/// root of unity. `values.len()` must be a power of two.
+fn fft<F: Field>(values: &mut [F], root: F) {
+    let n = values.len();
+    if n <= 1 {
+        return;
+    }
+
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let step = root.exp_usize(n / len);
+        let mut i = 0;
+        while i < n {
+            let mut w = F::ONE;
+            for k in 0..len / 2 {
+                let u = values[i + k];
+                let v = values[i + k + len / 2] * w;
+                values[i + k] = u + v;
+                values[i + k + len / 2] = u - v;
+                w = w * step;
+            }
+            i += len;
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ops::{Add, Mul, Sub};
+
+    /// A tiny field, `p = 769 = 2^8 * 3 + 1`, used only to exercise `EvaluationDomain`'s FFTs
+    /// without a real `Field` impl; `p - 1` having a factor of `2^8` is what makes it usable
+    /// as a domain generator for a handful of small-degree round trips.
+    #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+    struct TestField(i64);
+
+    const P: i64 = 769;
+    const GENERATOR: i64 = 11;
+
+    fn reduce(x: i64) -> i64 {
+        x.rem_euclid(P)
+    }
+
+    impl Add for TestField {
+        type Output = TestField;
+        fn add(self, rhs: TestField) -> TestField {
+            TestField(reduce(self.0 + rhs.0))
+        }
+    }
+
+    impl Sub for TestField {
+        type Output = TestField;
+        fn sub(self, rhs: TestField) -> TestField {
+            TestField(reduce(self.0 - rhs.0))
+        }
+    }
+
+    impl Mul for TestField {
+        type Output = TestField;
+        fn mul(self, rhs: TestField) -> TestField {
+            TestField(reduce(self.0 * rhs.0))
+        }
+    }
+
+    impl TestField {
+        fn exp_usize(self, power: usize) -> TestField {
+            let mut result = TestField::ONE;
+            let mut base = self;
+            let mut power = power;
+            while power > 0 {
+                if power & 1 == 1 {
+                    result = result * base;
+                }
+                base = base * base;
+                power >>= 1;
+            }
+            result
+        }
+
+        fn inverse(self) -> TestField {
+            // Fermat's little theorem: a^(p-2) is a's inverse mod a prime p.
+            self.exp_usize((P - 2) as usize)
+        }
+    }
+
+    impl Field for TestField {
+        const ZERO: TestField = TestField(0);
+        const ONE: TestField = TestField(1);
+        const TWO: TestField = TestField(2);
+        const NEG_ONE: TestField = TestField(P - 1);
+        const MULTIPLICATIVE_GROUP_GENERATOR: TestField = TestField(GENERATOR);
+        const BITS: usize = 64;
+
+        fn from_canonical_usize(n: usize) -> TestField {
+            TestField(reduce(n as i64))
+        }
+
+        fn inverse(&self) -> TestField {
+            TestField::inverse(*self)
+        }
+
+        fn exp_usize(&self, power: usize) -> TestField {
+            TestField::exp_usize(*self, power)
+        }
+
+        fn primitive_root_of_unity(n_log: usize) -> TestField {
+            // `(p - 1) / 2^n_log`-th power of a generator of the full multiplicative group is
+            // an element of order `2^n_log`, i.e. a primitive `2^n_log`-th root of unity.
+            let n = 1usize << n_log;
+            assert!((P as usize - 1) % n == 0, "domain size {} doesn't divide p - 1", n);
+            TestField::MULTIPLICATIVE_GROUP_GENERATOR.exp_usize(((P as usize) - 1) / n)
+        }
+    }
+
+    #[test]
+    fn fft_round_trip() {
+        let domain = EvaluationDomain::<TestField>::new(3, 0);
+        let coeffs: Vec<TestField> = (0..8).map(TestField::from_canonical_usize).collect();
+        let poly = Polynomial::from_coefficients(coeffs.clone());
+
+        let lagrange = domain.coeff_to_lagrange(poly);
+        let back = domain.lagrange_to_coeff(lagrange);
+
+        assert_eq!(back.values(), coeffs.as_slice());
+    }
+
+    #[test]
+    fn coset_fft_round_trip() {
+        let domain = EvaluationDomain::<TestField>::new(2, 1);
+        let coeffs: Vec<TestField> = (0..4).map(TestField::from_canonical_usize).collect();
+        let poly = Polynomial::from_coefficients(coeffs.clone());
+
+        let extended = domain.coeff_to_extended(poly);
+        let back = domain.extended_to_coeff(extended);
+
+        assert_eq!(back.values(), coeffs.as_slice());
+    }
+}
@@ -0,0 +1,54 @@
+use std::marker::PhantomData;
+
+use crate::Field;
+
+mod domain;
+
+pub use domain::*;
+
+/// Marker for the basis a `Polynomial`'s values are expressed in. Conversions between
+/// bases only happen through `EvaluationDomain`'s FFT/coset-FFT methods, so it's a type
+/// error to e.g. treat coset evaluations as if they were plain coefficients.
+pub trait Basis {}
+
+/// The monomial basis: `values[i]` is the coefficient of `X^i`.
+pub struct Coeff;
+impl Basis for Coeff {}
+
+/// Evaluations over the circuit's evaluation domain, the `degree`-th roots of unity.
+pub struct LagrangeCoeff;
+impl Basis for LagrangeCoeff {}
+
+/// Evaluations over a coset of a larger domain. Used while computing the quotient
+/// polynomial, so that products of wire/constant polynomials stay representable without
+/// needing a domain as large as their full product degree.
+pub struct ExtendedLagrangeCoeff;
+impl Basis for ExtendedLagrangeCoeff {}
+
+/// A polynomial tagged with the basis its `values` are expressed in.
+#[derive(Clone, Debug)]
+pub struct Polynomial<F: Field, B: Basis> {
+    values: Vec<F>,
+    _basis: PhantomData<B>,
+}
+
+impl<F: Field, B: Basis> Polynomial<F, B> {
+    pub(crate) fn new(values: Vec<F>) -> Self {
+        Polynomial { values, _basis: PhantomData }
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+}
+
+impl<F: Field> Polynomial<F, Coeff> {
+    /// Wraps `coefficients` as a `Coeff`-basis polynomial, with no domain association yet.
+    pub fn from_coefficients(coefficients: Vec<F>) -> Self {
+        Polynomial::new(coefficients)
+    }
+}